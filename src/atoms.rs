@@ -0,0 +1,127 @@
+
+// EWMH/ICCCM atoms this window manager interns once at startup and reuses
+// wherever a _NET_* or WM_* property needs to be read or written, modeled on
+// penrose's Atom/Prop split and the xcb-wm ewmh+icccm layers
+pub struct Atoms {
+    pub wm_protocols: xcb::Atom,
+    pub wm_delete_window: xcb::Atom,
+    pub wm_take_focus: xcb::Atom,
+    pub utf8_string: xcb::Atom,
+    pub net_supported: xcb::Atom,
+    pub net_client_list: xcb::Atom,
+    pub net_supporting_wm_check: xcb::Atom,
+    pub net_wm_name: xcb::Atom,
+    pub net_wm_window_type: xcb::Atom,
+    pub net_wm_window_type_dock: xcb::Atom,
+    pub net_wm_window_type_splash: xcb::Atom,
+    pub net_wm_window_type_notification: xcb::Atom,
+    pub net_wm_state: xcb::Atom,
+    pub net_wm_state_fullscreen: xcb::Atom,
+}
+
+impl Atoms {
+    pub fn intern(connection: &xcb::Connection) -> Atoms {
+        Atoms {
+            wm_protocols: intern(connection, "WM_PROTOCOLS"),
+            wm_delete_window: intern(connection, "WM_DELETE_WINDOW"),
+            wm_take_focus: intern(connection, "WM_TAKE_FOCUS"),
+            utf8_string: intern(connection, "UTF8_STRING"),
+            net_supported: intern(connection, "_NET_SUPPORTED"),
+            net_client_list: intern(connection, "_NET_CLIENT_LIST"),
+            net_supporting_wm_check: intern(connection, "_NET_SUPPORTING_WM_CHECK"),
+            net_wm_name: intern(connection, "_NET_WM_NAME"),
+            net_wm_window_type: intern(connection, "_NET_WM_WINDOW_TYPE"),
+            net_wm_window_type_dock: intern(connection, "_NET_WM_WINDOW_TYPE_DOCK"),
+            net_wm_window_type_splash: intern(connection, "_NET_WM_WINDOW_TYPE_SPLASH"),
+            net_wm_window_type_notification: intern(connection, "_NET_WM_WINDOW_TYPE_NOTIFICATION"),
+            net_wm_state: intern(connection, "_NET_WM_STATE"),
+            net_wm_state_fullscreen: intern(connection, "_NET_WM_STATE_FULLSCREEN"),
+        }
+    }
+
+    // the _NET_SUPPORTED list this window manager advertises to clients and panels
+    pub fn supported(&self) -> Vec<xcb::Atom> {
+        vec![
+            self.net_supported,
+            self.net_client_list,
+            self.net_supporting_wm_check,
+            self.net_wm_name,
+            self.net_wm_window_type,
+            self.net_wm_state,
+            self.net_wm_state_fullscreen,
+        ]
+    }
+}
+
+fn intern(connection: &xcb::Connection, name: &str) -> xcb::Atom {
+    xcb::intern_atom(connection, false, name)
+        .get_reply()
+        .expect("Could not intern atom")
+        .atom()
+}
+
+// typed wrappers around get_property/change_property for the handful of
+// property shapes this window manager cares about
+
+pub fn get_atom_list(
+    connection: &xcb::Connection,
+    window: xcb::Window,
+    property: xcb::Atom,
+) -> Vec<xcb::Atom> {
+    xcb::get_property(connection, false, window, property, xcb::ATOM_ATOM, 0, 1024)
+        .get_reply()
+        .map(|reply| reply.value::<xcb::Atom>().to_vec())
+        .unwrap_or_default()
+}
+
+pub fn set_atom_list(
+    connection: &xcb::Connection,
+    window: xcb::Window,
+    property: xcb::Atom,
+    atoms: &[xcb::Atom],
+) {
+    xcb::change_property(
+        connection,
+        xcb::PROP_MODE_REPLACE as u8,
+        window,
+        property,
+        xcb::ATOM_ATOM,
+        32,
+        atoms,
+    );
+}
+
+pub fn set_window_list(
+    connection: &xcb::Connection,
+    window: xcb::Window,
+    property: xcb::Atom,
+    windows: &[xcb::Window],
+) {
+    xcb::change_property(
+        connection,
+        xcb::PROP_MODE_REPLACE as u8,
+        window,
+        property,
+        xcb::ATOM_WINDOW,
+        32,
+        windows,
+    );
+}
+
+pub fn set_utf8_string(
+    connection: &xcb::Connection,
+    window: xcb::Window,
+    property: xcb::Atom,
+    utf8_string: xcb::Atom,
+    value: &str,
+) {
+    xcb::change_property(
+        connection,
+        xcb::PROP_MODE_REPLACE as u8,
+        window,
+        property,
+        utf8_string,
+        8,
+        value.as_bytes(),
+    );
+}
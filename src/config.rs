@@ -0,0 +1,195 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::layout::Layout;
+
+// an action a keybinding can trigger, read out of the config file's
+// [[keybindings]] table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Close,
+    CycleFocus,
+    CycleLayout,
+    PromoteToMaster,
+    ToggleFloating,
+    Spawn(String),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutName {
+    MasterStack,
+    Monocle,
+}
+
+impl From<LayoutName> for Layout {
+    fn from(name: LayoutName) -> Layout {
+        match name {
+            LayoutName::MasterStack => Layout::MasterStack,
+            LayoutName::Monocle => Layout::Monocle,
+        }
+    }
+}
+
+// a single keysym+modifier -> action binding as written in the config file,
+// e.g. `{ modifier = "mod1", key = "tab", action = "cycle_focus" }`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Keybinding {
+    pub modifier: String,
+    pub key: String,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub border_width: u32,
+    pub border_color: u32,
+    pub border_color_focused: u32,
+    pub bg_color: u32,
+    // the modifier held down for window move/resize drags
+    pub modifier: String,
+    pub layout: LayoutName,
+    pub master_ratio: f32,
+    pub keybindings: Vec<Keybinding>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            border_width: 4,
+            border_color: 0xff0000,
+            border_color_focused: 0x00ff00,
+            bg_color: 0x0000ff,
+            modifier: "mod1".to_string(),
+            layout: LayoutName::MasterStack,
+            master_ratio: 0.5,
+            keybindings: vec![
+                Keybinding {
+                    modifier: "mod1".to_string(),
+                    key: "f4".to_string(),
+                    action: Action::Close,
+                },
+                Keybinding {
+                    modifier: "mod1".to_string(),
+                    key: "tab".to_string(),
+                    action: Action::CycleFocus,
+                },
+                Keybinding {
+                    modifier: "mod1".to_string(),
+                    key: "space".to_string(),
+                    action: Action::CycleLayout,
+                },
+                Keybinding {
+                    modifier: "mod1+shift".to_string(),
+                    key: "return".to_string(),
+                    action: Action::PromoteToMaster,
+                },
+                // puts a window back into tiling after a Mod1-drag floated it
+                Keybinding {
+                    modifier: "mod1+shift".to_string(),
+                    key: "f".to_string(),
+                    action: Action::ToggleFloating,
+                },
+            ],
+        }
+    }
+}
+
+impl Config {
+    // load ~/.config/xcb_test_wm/config.toml, falling back to the defaults
+    // above for anything unset or if the file doesn't exist at all
+    pub fn load() -> Config {
+        let path = config_path();
+
+        let source = ::config::Config::builder()
+            .add_source(::config::File::from(path).required(false))
+            .build();
+
+        source
+            .and_then(|source| source.try_deserialize())
+            .unwrap_or_default()
+    }
+}
+
+fn config_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home)
+        .join(".config")
+        .join("xcb_test_wm")
+        .join("config.toml")
+}
+
+// turn a config modifier spec like "mod1" or "mod1+shift" into an X11
+// modifier mask
+pub fn resolve_modifier(spec: &str) -> u16 {
+    spec.split('+')
+        .map(|part| match part.trim() {
+            "mod1" | "alt" => xcb::MOD_MASK_1,
+            "mod4" | "super" => xcb::MOD_MASK_4,
+            "shift" => xcb::MOD_MASK_SHIFT,
+            "control" | "ctrl" => xcb::MOD_MASK_CONTROL,
+            _ => 0,
+        })
+        .fold(0, |mask, bit| mask | bit) as u16
+}
+
+// turn a config key name like "tab" or "f4" into a keysym; single
+// alphanumeric characters map directly since their keysym equals their ASCII
+// code
+pub fn resolve_keysym(name: &str) -> Option<u32> {
+    Some(match name {
+        "f4" => x11::keysym::XK_F4,
+        "tab" => x11::keysym::XK_Tab,
+        "space" => x11::keysym::XK_space,
+        "return" | "enter" => x11::keysym::XK_Return,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => c as u32,
+                _ => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_modifier_handles_a_single_part() {
+        assert_eq!(resolve_modifier("mod1"), xcb::MOD_MASK_1 as u16);
+        assert_eq!(resolve_modifier("alt"), xcb::MOD_MASK_1 as u16);
+    }
+
+    #[test]
+    fn resolve_modifier_ors_together_combined_parts() {
+        let expected = (xcb::MOD_MASK_1 | xcb::MOD_MASK_SHIFT) as u16;
+        assert_eq!(resolve_modifier("mod1+shift"), expected);
+    }
+
+    #[test]
+    fn resolve_modifier_ignores_unknown_parts() {
+        assert_eq!(resolve_modifier("nonsense"), 0);
+    }
+
+    #[test]
+    fn resolve_keysym_knows_the_named_keys() {
+        assert_eq!(resolve_keysym("tab"), Some(x11::keysym::XK_Tab));
+        assert_eq!(resolve_keysym("f4"), Some(x11::keysym::XK_F4));
+        assert_eq!(resolve_keysym("enter"), Some(x11::keysym::XK_Return));
+    }
+
+    #[test]
+    fn resolve_keysym_maps_a_single_alphanumeric_character() {
+        assert_eq!(resolve_keysym("f"), Some('f' as u32));
+    }
+
+    #[test]
+    fn resolve_keysym_rejects_anything_else() {
+        assert_eq!(resolve_keysym("nonsense"), None);
+        assert_eq!(resolve_keysym(""), None);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::screen::Region;
+
+// the tiling layouts this window manager can arrange a screen's clients
+// with; toggled with Mod1+Space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    // one resizable master column plus a stacked secondary column
+    MasterStack,
+    // a single client fills the whole screen
+    Monocle,
+}
+
+impl Layout {
+    pub fn next(self) -> Layout {
+        match self {
+            Layout::MasterStack => Layout::Monocle,
+            Layout::Monocle => Layout::MasterStack,
+        }
+    }
+
+    // assign each client a tile within `screen`; `master_ratio` is the
+    // fraction of the screen's width given to the master column and only
+    // matters for MasterStack
+    pub fn arrange(
+        self,
+        screen: Region,
+        clients: &[xcb::Window],
+        master_ratio: f32,
+    ) -> Vec<(xcb::Window, Region)> {
+        match self {
+            Layout::Monocle => clients.iter().map(|&client| (client, screen)).collect(),
+            Layout::MasterStack => master_stack(screen, clients, master_ratio),
+        }
+    }
+}
+
+fn master_stack(
+    screen: Region,
+    clients: &[xcb::Window],
+    master_ratio: f32,
+) -> Vec<(xcb::Window, Region)> {
+    if clients.is_empty() {
+        return Vec::new();
+    }
+
+    if clients.len() == 1 {
+        return vec![(clients[0], screen)];
+    }
+
+    // clamp so a bad config value (e.g. > 1.0) can never make master_width
+    // exceed the screen width and underflow stack_width below
+    let master_ratio = master_ratio.clamp(0.05, 0.95);
+
+    let master_width = (screen.width as f32 * master_ratio) as u32;
+    let stack_width = screen.width - master_width;
+    let stack_count = clients.len() - 1;
+    let stack_height = screen.height / stack_count as u32;
+    // give the remainder from that division to the last tile instead of
+    // leaving it as uncovered background
+    let stack_height_remainder = screen.height % stack_count as u32;
+
+    let mut tiles = vec![(
+        clients[0],
+        Region {
+            x: screen.x,
+            y: screen.y,
+            width: master_width,
+            height: screen.height,
+        },
+    )];
+
+    for (i, &client) in clients[1..].iter().enumerate() {
+        let height = if i + 1 == stack_count {
+            stack_height + stack_height_remainder
+        } else {
+            stack_height
+        };
+
+        tiles.push((
+            client,
+            Region {
+                x: screen.x + master_width as i32,
+                y: screen.y + (i as u32 * stack_height) as i32,
+                width: stack_width,
+                height,
+            },
+        ));
+    }
+
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen() -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: 1000,
+            height: 1000,
+        }
+    }
+
+    #[test]
+    fn no_clients_produces_no_tiles() {
+        assert_eq!(master_stack(screen(), &[], 0.5), Vec::new());
+    }
+
+    #[test]
+    fn a_single_client_fills_the_whole_screen() {
+        assert_eq!(master_stack(screen(), &[1], 0.5), vec![(1, screen())]);
+    }
+
+    #[test]
+    fn master_gets_the_configured_fraction_of_the_width() {
+        let tiles = master_stack(screen(), &[1, 2], 0.5);
+        assert_eq!(tiles[0].1.width, 500);
+        assert_eq!(tiles[1].1.width, 500);
+    }
+
+    #[test]
+    fn an_out_of_range_ratio_is_clamped_instead_of_underflowing() {
+        let tiles = master_stack(screen(), &[1, 2], 1.5);
+        assert_eq!(tiles[0].1.width, 950);
+        assert_eq!(tiles[1].1.width, 50);
+    }
+
+    #[test]
+    fn the_stack_height_remainder_goes_to_the_last_tile() {
+        let tiles = master_stack(screen(), &[1, 2, 3, 4], 0.5);
+        // 3 stacked clients splitting 1000px: 333, 333, 334
+        assert_eq!(tiles[1].1.height, 333);
+        assert_eq!(tiles[2].1.height, 333);
+        assert_eq!(tiles[3].1.height, 334);
+
+        let total: u32 = tiles[1..].iter().map(|(_, region)| region.height).sum();
+        assert_eq!(total, screen().height);
+    }
+}
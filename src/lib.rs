@@ -1,15 +1,94 @@
-use std::collections::HashMap;
-use x11;
-use xcb;
+use std::collections::{HashMap, HashSet};
 use xcb_util::keysyms;
 
+mod atoms;
+mod config;
+mod layout;
+mod screen;
+
+use atoms::Atoms;
+use config::{Action, Config};
+use layout::Layout;
+use screen::{Point, Region};
+
+// a keybinding from the config file with its key name resolved to a keysym,
+// ready to grab/match without touching the config again
+#[derive(Debug, Clone)]
+struct ResolvedBinding {
+    modifier_mask: u16,
+    keysym: xcb::Keysym,
+    action: Action,
+}
+
+// which edge of the grabbed button drives the drag: move on button 1,
+// resize on button 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DragKind {
+    Move,
+    Resize,
+}
+
+// anchor state captured on BUTTON_PRESS and consulted on every MOTION_NOTIFY
+// until the matching BUTTON_RELEASE
+#[derive(Debug, Clone, Copy)]
+struct DragState {
+    kind: DragKind,
+    client: xcb::Window,
+    frame: xcb::Window,
+    // root-relative pointer position at press time
+    anchor_x: i32,
+    anchor_y: i32,
+    // frame geometry at press time
+    frame_x: i32,
+    frame_y: i32,
+    frame_width: u32,
+    frame_height: u32,
+}
+
+// advertised to _NET_WM_NAME on the supporting WM check window
+const WM_NAME: &str = "xcb_test_wm";
+
+// modifier bits X11 sets in event.state() for the lock keys but that have no
+// bearing on which binding was meant; masked out before matching and grabbed
+// for every combination so bindings keep firing with NumLock/CapsLock on
+const IGNORED_MODIFIERS: u16 = (xcb::MOD_MASK_LOCK | xcb::MOD_MASK_2) as u16;
+
+fn modifier_variants(mask: u16) -> [u16; 4] {
+    let lock = xcb::MOD_MASK_LOCK as u16;
+    let num_lock = xcb::MOD_MASK_2 as u16;
+    [mask, mask | lock, mask | num_lock, mask | lock | num_lock]
+}
+
 pub struct WindowManager {
     connection: xcb::Connection,
     root: xcb::Window,
     clients: HashMap<xcb::Window, xcb::Window>,
+    // client windows in map order, oldest first, except that promote_to_master
+    // moves a client to the front; used as the tiling order, the Alt+Tab ring
+    // and to publish _NET_CLIENT_LIST
+    client_order: Vec<xcb::Window>,
+    focused: Option<xcb::Window>,
+    drag: Option<DragState>,
+    atoms: Atoms,
+    // per-monitor geometry, refreshed on RandR screen-change notify
+    screens: Vec<Region>,
+    randr_first_event: u8,
+    layout: Layout,
+    master_ratio: f32,
+    // windows excluded from tiling, e.g. because they were Mod1-dragged
+    floating: HashSet<xcb::Window>,
+    // which self.screens index each managed client is tiled on
+    client_screen: HashMap<xcb::Window, usize>,
+    config: Config,
+    // the drag modifier, resolved once from config.modifier
+    modifier_mask: u16,
+    keybindings: Vec<ResolvedBinding>,
 }
 
 impl WindowManager {
+    // not a Default impl: connecting to the X display is a side effect that
+    // can fail, which doesn't fit Default's infallible-and-cheap contract
+    #[allow(clippy::new_without_default)]
     pub fn new() -> WindowManager {
         // Connect to default display
         let (connection, root_idx) =
@@ -25,10 +104,91 @@ impl WindowManager {
 
         let clients = HashMap::new();
 
+        let atoms = Atoms::intern(&connection);
+
+        let supporting_wm_check = connection.generate_id();
+        xcb::create_window(
+            &connection,
+            xcb::COPY_FROM_PARENT as u8,
+            supporting_wm_check,
+            root,
+            -1,
+            -1,
+            1,
+            1,
+            0,
+            xcb::WINDOW_CLASS_INPUT_OUTPUT as u16,
+            xcb::COPY_FROM_PARENT,
+            &[],
+        );
+        atoms::set_window_list(
+            &connection,
+            supporting_wm_check,
+            atoms.net_supporting_wm_check,
+            &[supporting_wm_check],
+        );
+        atoms::set_window_list(
+            &connection,
+            root,
+            atoms.net_supporting_wm_check,
+            &[supporting_wm_check],
+        );
+        atoms::set_utf8_string(
+            &connection,
+            supporting_wm_check,
+            atoms.net_wm_name,
+            atoms.utf8_string,
+            WM_NAME,
+        );
+        atoms::set_atom_list(&connection, root, atoms.net_supported, &atoms.supported());
+        atoms::set_window_list(&connection, root, atoms.net_client_list, &[]);
+
+        let randr_first_event = connection
+            .get_extension_data(xcb::randr::id())
+            .expect("RandR extension is not available")
+            .first_event();
+
+        xcb::randr::select_input(
+            &connection,
+            root,
+            xcb::randr::NOTIFY_MASK_SCREEN_CHANGE as u16,
+        );
+
+        let screens = screen::query_screens(&connection, root);
+
+        let config = Config::load();
+        let modifier_mask = config::resolve_modifier(&config.modifier);
+        let keybindings: Vec<ResolvedBinding> = config
+            .keybindings
+            .iter()
+            .filter_map(|binding| {
+                config::resolve_keysym(&binding.key).map(|keysym| ResolvedBinding {
+                    modifier_mask: config::resolve_modifier(&binding.modifier),
+                    keysym,
+                    action: binding.action.clone(),
+                })
+            })
+            .collect();
+        let layout = config.layout.into();
+        let master_ratio = config.master_ratio;
+
         WindowManager {
             connection,
             root,
             clients,
+            client_order: Vec::new(),
+            focused: None,
+            drag: None,
+            atoms,
+            screens,
+            randr_first_event,
+            layout,
+            master_ratio,
+            floating: HashSet::new(),
+            client_screen: HashMap::new(),
+            config,
+            modifier_mask,
+            keybindings,
         }
     }
 
@@ -50,9 +210,7 @@ impl WindowManager {
             .get_reply()
             .expect("Could not query existing windows")
             .children()
-            .iter()
-            .map(|w| *w)
-            .collect();
+            .to_vec();
 
         for window in existing_windows {
             self.frame_window(window, true);
@@ -66,12 +224,24 @@ impl WindowManager {
                 .connection
                 .wait_for_event()
                 .expect("Error receiving event");
+
+            let response_type = e.response_type();
+            if response_type == self.randr_first_event + xcb::randr::SCREEN_CHANGE_NOTIFY {
+                unsafe {
+                    self.on_screen_change_notify(xcb::cast_event(&e));
+                }
+                continue;
+            }
+
             unsafe {
-                match e.response_type() {
+                match response_type {
                     xcb::CONFIGURE_REQUEST => self.on_configure_request(xcb::cast_event(&e)),
                     xcb::MAP_REQUEST => self.on_map_request(xcb::cast_event(&e)),
                     xcb::UNMAP_NOTIFY => self.on_unmap_notify(xcb::cast_event(&e)),
                     xcb::BUTTON_PRESS => self.on_button_press(xcb::cast_event(&e)),
+                    xcb::BUTTON_RELEASE => self.on_button_release(xcb::cast_event(&e)),
+                    xcb::MOTION_NOTIFY => self.on_motion_notify(xcb::cast_event(&e)),
+                    xcb::KEY_PRESS => self.on_key_press(xcb::cast_event(&e)),
                     _ => continue,
                 };
             }
@@ -89,7 +259,7 @@ impl WindowManager {
                 xcb::CONFIG_WINDOW_BORDER_WIDTH as u16,
                 event.border_width() as u32,
             ),
-            (xcb::CONFIG_WINDOW_SIBLING as u16, event.sibling() as u32),
+            (xcb::CONFIG_WINDOW_SIBLING as u16, event.sibling()),
             (
                 xcb::CONFIG_WINDOW_STACK_MODE as u16,
                 event.stack_mode() as u32,
@@ -128,35 +298,78 @@ impl WindowManager {
             }
         }
 
-        let border_width = 4;
-        let border_color = 0xff0000;
-        let bg_color = 0x0000ff;
+        // panels, docks, and notifications manage their own placement; map
+        // them directly instead of wrapping them in a frame
+        let window_type =
+            atoms::get_atom_list(&self.connection, window, self.atoms.net_wm_window_type);
+        if window_type.contains(&self.atoms.net_wm_window_type_dock)
+            || window_type.contains(&self.atoms.net_wm_window_type_splash)
+            || window_type.contains(&self.atoms.net_wm_window_type_notification)
+        {
+            xcb::map_window(&self.connection, window);
+            return;
+        }
+
+        let border_width = self.config.border_width;
+        let border_color = self.config.border_color;
+        let bg_color = self.config.bg_color;
 
         let wid = self.connection.generate_id();
         let geo = xcb::get_geometry(&self.connection, window)
             .get_reply()
             .expect("Could not get geometry of parent window");
 
+        let fullscreen = atoms::get_atom_list(&self.connection, window, self.atoms.net_wm_state)
+            .contains(&self.atoms.net_wm_state_fullscreen);
+
+        let anchor = if geo.x() == 0 && geo.y() == 0 {
+            self.pointer_position()
+        } else {
+            Point {
+                x: geo.x() as i32,
+                y: geo.y() as i32,
+            }
+        };
+        let screen_index = self.screen_index_for_point(anchor);
+        let screen = self.screens[screen_index];
+
+        let (frame_x, frame_y, frame_width, frame_height) = if fullscreen {
+            (
+                screen.x as i16,
+                screen.y as i16,
+                screen.width as u16,
+                screen.height as u16,
+            )
+        } else {
+            let width = geo.width();
+            let height = geo.height();
+            let max_x = screen.x + screen.width as i32 - width as i32;
+            let max_y = screen.y + screen.height as i32 - height as i32;
+            let x = (geo.x() as i32).max(screen.x).min(max_x.max(screen.x));
+            let y = (geo.y() as i32).max(screen.y).min(max_y.max(screen.y));
+            (x as i16, y as i16, width, height)
+        };
+
         // creates border window with above options
         xcb::create_window(
             &self.connection,
             xcb::COPY_FROM_PARENT as u8,
             wid,
             self.root,
-            geo.x(),
-            geo.y(),
-            geo.width(),
-            geo.height(),
-            border_width,
+            frame_x,
+            frame_y,
+            frame_width,
+            frame_height,
+            border_width as u16,
             xcb::WINDOW_CLASS_INPUT_OUTPUT as u16,
             xcb::COPY_FROM_PARENT,
-            &vec![],
+            &[],
         );
 
         // change border color
         let value_list = vec![
-            (xcb::CW_BORDER_PIXEL as u16, border_color as u32),
-            (xcb::CW_BACK_PIXEL as u16, bg_color as u32),
+            (xcb::CW_BORDER_PIXEL as u16, border_color),
+            (xcb::CW_BACK_PIXEL as u16, bg_color),
         ];
         xcb::configure_window(&self.connection, wid, &value_list);
 
@@ -174,77 +387,88 @@ impl WindowManager {
 
         xcb::reparent_window(&self.connection, window, wid, 0, 0);
 
+        if fullscreen {
+            // the frame was blown up to the monitor's geometry above; the
+            // client needs to fill it too instead of staying pinned at its
+            // original size
+            let value_list = vec![
+                (xcb::CONFIG_WINDOW_WIDTH as u16, frame_width as u32),
+                (xcb::CONFIG_WINDOW_HEIGHT as u16, frame_height as u32),
+            ];
+            xcb::configure_window(&self.connection, window, &value_list);
+        }
+
         xcb::map_window(&self.connection, wid);
 
-        self.clients.insert(window, wid).unwrap();
+        self.clients.insert(window, wid);
+        self.client_order.push(window);
+        self.client_screen.insert(window, screen_index);
+        if fullscreen {
+            // fullscreen is its own layout; don't let tiling move it
+            self.floating.insert(window);
+        }
+        self.update_net_client_list();
+        self.focus_client(window);
+        self.arrange();
 
         let key_symbols = keysyms::KeySymbols::new(&self.connection);
 
-        // allows window to be moved with mod1 + left mouse button
-        xcb::grab_button(
-            &self.connection,
-            false,
-            window,
-            xcb::EVENT_MASK_BUTTON_PRESS as u16
-                | xcb::EVENT_MASK_BUTTON_RELEASE as u16
-                | xcb::EVENT_MASK_BUTTON_MOTION as u16,
-            xcb::GRAB_MODE_ASYNC as u8,
-            xcb::GRAB_MODE_ASYNC as u8,
-            xcb::NONE,
-            xcb::NONE,
-            xcb::BUTTON_INDEX_1 as u8,
-            xcb::MOD_MASK_1 as u16,
-        );
-
-        // allows window to be resized with mod1 + right mouse button
-        xcb::grab_button(
-            &self.connection,
-            false,
-            window,
-            xcb::EVENT_MASK_BUTTON_PRESS as u16
-                | xcb::EVENT_MASK_BUTTON_RELEASE as u16
-                | xcb::EVENT_MASK_BUTTON_MOTION as u16,
-            xcb::GRAB_MODE_ASYNC as u8,
-            xcb::GRAB_MODE_ASYNC as u8,
-            xcb::NONE,
-            xcb::NONE,
-            xcb::BUTTON_INDEX_3 as u8,
-            xcb::MOD_MASK_1 as u16,
-        );
-
-        // allows window to be closed with alt + f4
-        xcb::grab_key(
-            &self.connection,
-            false,
-            window,
-            xcb::MOD_MASK_1 as u16,
-            match key_symbols.get_keycode(x11::keysym::XK_F4).next() {
-                Some(keycode) => keycode,
-                None => panic!("Could not resolve keysym"),
-            },
-            xcb::GRAB_MODE_ASYNC as u8,
-            xcb::GRAB_MODE_ASYNC as u8,
-        );
+        // allows window to be moved/resized with the configured modifier +
+        // left/right mouse button; grabbed once per lock-modifier
+        // combination so NumLock/CapsLock being held doesn't swallow it
+        for modifiers in modifier_variants(self.modifier_mask) {
+            xcb::grab_button(
+                &self.connection,
+                false,
+                window,
+                xcb::EVENT_MASK_BUTTON_PRESS as u16
+                    | xcb::EVENT_MASK_BUTTON_RELEASE as u16
+                    | xcb::EVENT_MASK_BUTTON_MOTION as u16,
+                xcb::GRAB_MODE_ASYNC as u8,
+                xcb::GRAB_MODE_ASYNC as u8,
+                xcb::NONE,
+                xcb::NONE,
+                xcb::BUTTON_INDEX_1 as u8,
+                modifiers,
+            );
+
+            xcb::grab_button(
+                &self.connection,
+                false,
+                window,
+                xcb::EVENT_MASK_BUTTON_PRESS as u16
+                    | xcb::EVENT_MASK_BUTTON_RELEASE as u16
+                    | xcb::EVENT_MASK_BUTTON_MOTION as u16,
+                xcb::GRAB_MODE_ASYNC as u8,
+                xcb::GRAB_MODE_ASYNC as u8,
+                xcb::NONE,
+                xcb::NONE,
+                xcb::BUTTON_INDEX_3 as u8,
+                modifiers,
+            );
+        }
 
-        // allows window to be switched with alt + tab
-        xcb::grab_key(
-            &self.connection,
-            false,
-            window,
-            xcb::MOD_MASK_1 as u16,
-            match key_symbols.get_keycode(x11::keysym::XK_Tab).next() {
-                Some(keycode) => keycode,
-                None => panic!("Could not resolve keysym"),
-            },
-            xcb::GRAB_MODE_ASYNC as u8,
-            xcb::GRAB_MODE_ASYNC as u8,
-        );
+        // grab every keybinding read from the config file, likewise once per
+        // lock-modifier combination
+        for binding in &self.keybindings {
+            if let Some(keycode) = key_symbols.get_keycode(binding.keysym).next() {
+                for modifiers in modifier_variants(binding.modifier_mask) {
+                    xcb::grab_key(
+                        &self.connection,
+                        false,
+                        window,
+                        modifiers,
+                        keycode,
+                        xcb::GRAB_MODE_ASYNC as u8,
+                        xcb::GRAB_MODE_ASYNC as u8,
+                    );
+                }
+            }
+        }
     }
 
     fn on_unmap_notify(&mut self, event: &xcb::UnmapNotifyEvent) {
-        if !self.clients.contains_key(&event.window()) {
-            return;
-        } else if event.event() == self.root {
+        if !self.clients.contains_key(&event.window()) || event.event() == self.root {
             return;
         }
 
@@ -266,9 +490,372 @@ impl WindowManager {
         xcb::destroy_window(&self.connection, *frame);
 
         self.clients.remove(&window);
+        self.client_order.retain(|&w| w != window);
+        self.client_screen.remove(&window);
+        self.floating.remove(&window);
+        self.update_net_client_list();
+
+        if self.focused == Some(window) {
+            self.focused = None;
+            if let Some(&next) = self.client_order.first() {
+                self.focus_client(next);
+            }
+        }
+
+        self.arrange();
+    }
+
+    fn on_button_press(&mut self, event: &xcb::ButtonPressEvent) {
+        let frame = match self.clients.get(&event.event()) {
+            Some(frame) => *frame,
+            None => return,
+        };
+
+        let kind = match event.detail() as u32 {
+            xcb::BUTTON_INDEX_1 => DragKind::Move,
+            xcb::BUTTON_INDEX_3 => DragKind::Resize,
+            _ => return,
+        };
+
+        let geo = xcb::get_geometry(&self.connection, frame)
+            .get_reply()
+            .expect("Could not get geometry of frame window");
+
+        self.drag = Some(DragState {
+            kind,
+            client: event.event(),
+            frame,
+            anchor_x: event.root_x() as i32,
+            anchor_y: event.root_y() as i32,
+            frame_x: geo.x() as i32,
+            frame_y: geo.y() as i32,
+            frame_width: geo.width() as u32,
+            frame_height: geo.height() as u32,
+        });
+    }
+
+    fn on_motion_notify(&self, event: &xcb::MotionNotifyEvent) {
+        let drag = match self.drag {
+            Some(drag) => drag,
+            None => return,
+        };
+
+        let dx = event.root_x() as i32 - drag.anchor_x;
+        let dy = event.root_y() as i32 - drag.anchor_y;
+
+        match drag.kind {
+            DragKind::Move => {
+                let value_list = vec![
+                    (xcb::CONFIG_WINDOW_X as u16, (drag.frame_x + dx) as u32),
+                    (xcb::CONFIG_WINDOW_Y as u16, (drag.frame_y + dy) as u32),
+                ];
+                xcb::configure_window(&self.connection, drag.frame, &value_list);
+            }
+            DragKind::Resize => {
+                let width = std::cmp::max(1, drag.frame_width as i32 + dx) as u32;
+                let height = std::cmp::max(1, drag.frame_height as i32 + dy) as u32;
+
+                let value_list = vec![
+                    (xcb::CONFIG_WINDOW_WIDTH as u16, width),
+                    (xcb::CONFIG_WINDOW_HEIGHT as u16, height),
+                ];
+                xcb::configure_window(&self.connection, drag.frame, &value_list);
+                // the client fills the frame's interior, so it needs to grow
+                // and shrink along with it
+                xcb::configure_window(&self.connection, drag.client, &value_list);
+            }
+        }
+    }
+
+    fn on_button_release(&mut self, event: &xcb::ButtonReleaseEvent) {
+        if let Some(drag) = self.drag.take() {
+            // a click with no movement isn't a drag; only pull the window out
+            // of tiling if the pointer actually went somewhere
+            let moved = event.root_x() as i32 != drag.anchor_x
+                || event.root_y() as i32 != drag.anchor_y;
+            if moved {
+                self.floating.insert(drag.client);
+                self.arrange();
+            }
+        }
     }
 
-    fn on_button_press(&self, event: &xcb::ButtonPressEvent){
-        
+    fn on_key_press(&mut self, event: &xcb::KeyPressEvent) {
+        let keysym = {
+            let key_symbols = keysyms::KeySymbols::new(&self.connection);
+            key_symbols.get_keysym(event.detail(), 0)
+        };
+        // lock modifiers (NumLock/CapsLock) ride along in event.state() but
+        // aren't part of any binding's mask, so they're masked out here
+        let modifiers = event.state() & !IGNORED_MODIFIERS;
+
+        let action = self
+            .keybindings
+            .iter()
+            .find(|binding| binding.keysym == keysym && binding.modifier_mask == modifiers)
+            .map(|binding| binding.action.clone());
+
+        if let Some(action) = action {
+            self.dispatch_action(action, event.event());
+        }
+    }
+
+    fn dispatch_action(&mut self, action: Action, window: xcb::Window) {
+        match action {
+            Action::Close => self.close_window(window),
+            Action::CycleFocus => self.cycle_focus(),
+            Action::CycleLayout => self.cycle_layout(),
+            Action::PromoteToMaster => self.promote_to_master(window),
+            Action::ToggleFloating => self.toggle_floating(window),
+            Action::Spawn(command) => {
+                let _ = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .spawn();
+            }
+        }
+    }
+
+    // atoms a client listed in its WM_PROTOCOLS property
+    fn wm_protocols_of(&self, window: xcb::Window) -> Vec<xcb::Atom> {
+        atoms::get_atom_list(&self.connection, window, self.atoms.wm_protocols)
+    }
+
+    // ICCCM-compliant close: ask the client to save its state via
+    // WM_DELETE_WINDOW if it advertises support, otherwise just kill it
+    fn close_window(&self, window: xcb::Window) {
+        if self
+            .wm_protocols_of(window)
+            .contains(&self.atoms.wm_delete_window)
+        {
+            self.send_protocol_message(window, self.atoms.wm_delete_window);
+        } else {
+            xcb::kill_client(&self.connection, window);
+        }
+    }
+
+    fn send_protocol_message(&self, window: xcb::Window, protocol: xcb::Atom) {
+        let data = xcb::ClientMessageData::from_data32([protocol, xcb::CURRENT_TIME, 0, 0, 0]);
+        let event = xcb::ClientMessageEvent::new(32, window, self.atoms.wm_protocols, data);
+        xcb::send_event(
+            &self.connection,
+            false,
+            window,
+            xcb::EVENT_MASK_NO_EVENT,
+            &event,
+        );
+        self.connection.flush();
+    }
+
+    // advance the Alt+Tab ring to the client after the currently focused one
+    fn cycle_focus(&mut self) {
+        if self.client_order.is_empty() {
+            return;
+        }
+
+        let next = match self.focused {
+            Some(current) => {
+                let pos = self
+                    .client_order
+                    .iter()
+                    .position(|&w| w == current)
+                    .unwrap_or(0);
+                self.client_order[(pos + 1) % self.client_order.len()]
+            }
+            None => self.client_order[0],
+        };
+
+        self.focus_client(next);
+    }
+
+    // raise a client's frame and give it input focus, honoring WM_HINTS'
+    // input flag and the WM_TAKE_FOCUS protocol like a well-behaved EWMH/ICCCM
+    // window manager, and recolor borders to mark the focus change
+    fn focus_client(&mut self, window: xcb::Window) {
+        let frame = match self.clients.get(&window) {
+            Some(&frame) => frame,
+            None => return,
+        };
+
+        if let Some(previous) = self.focused {
+            if previous != window {
+                if let Some(&previous_frame) = self.clients.get(&previous) {
+                    self.set_border_color(previous_frame, self.config.border_color);
+                }
+            }
+        }
+
+        let value_list = vec![(
+            xcb::CONFIG_WINDOW_STACK_MODE as u16,
+            xcb::STACK_MODE_ABOVE,
+        )];
+        xcb::configure_window(&self.connection, frame, &value_list);
+
+        let accepts_input = xcb::get_property(
+            &self.connection,
+            false,
+            window,
+            xcb::ATOM_WM_HINTS,
+            xcb::ATOM_WM_HINTS,
+            0,
+            9,
+        )
+        .get_reply()
+        .map(|reply| {
+            let hints = reply.value::<u32>();
+            // ICCCM WMHints: flags at 0, input at 1; InputHint = 1 << 0
+            hints.len() < 2 || hints[0] & 1 == 0 || hints[1] != 0
+        })
+        .unwrap_or(true);
+
+        if accepts_input {
+            xcb::set_input_focus(
+                &self.connection,
+                xcb::INPUT_FOCUS_POINTER_ROOT as u8,
+                window,
+                xcb::CURRENT_TIME,
+            );
+        }
+
+        if self
+            .wm_protocols_of(window)
+            .contains(&self.atoms.wm_take_focus)
+        {
+            self.send_protocol_message(window, self.atoms.wm_take_focus);
+        }
+
+        self.set_border_color(frame, self.config.border_color_focused);
+        self.focused = Some(window);
+    }
+
+    fn set_border_color(&self, frame: xcb::Window, color: u32) {
+        let value_list = vec![(xcb::CW_BORDER_PIXEL, color)];
+        xcb::change_window_attributes(&self.connection, frame, &value_list);
+    }
+
+    fn update_net_client_list(&self) {
+        atoms::set_window_list(
+            &self.connection,
+            self.root,
+            self.atoms.net_client_list,
+            &self.client_order,
+        );
+    }
+
+    fn on_screen_change_notify(&mut self, _event: &xcb::randr::ScreenChangeNotifyEvent) {
+        self.screens = screen::query_screens(&self.connection, self.root);
+
+        // a hotplug can shrink the number of monitors, stranding any client
+        // whose client_screen index no longer exists; re-derive each one
+        // from the frame's current position before re-tiling
+        let frames: Vec<(xcb::Window, xcb::Window)> =
+            self.clients.iter().map(|(&client, &frame)| (client, frame)).collect();
+
+        for (client, frame) in frames {
+            let geo = xcb::get_geometry(&self.connection, frame)
+                .get_reply()
+                .expect("Could not get geometry of frame window");
+
+            let point = Point {
+                x: geo.x() as i32,
+                y: geo.y() as i32,
+            };
+            let index = self.screen_index_for_point(point);
+            self.client_screen.insert(client, index);
+        }
+
+        self.arrange();
+    }
+
+    fn pointer_position(&self) -> Point {
+        let reply = xcb::query_pointer(&self.connection, self.root)
+            .get_reply()
+            .expect("Could not query pointer position");
+
+        Point {
+            x: reply.root_x() as i32,
+            y: reply.root_y() as i32,
+        }
+    }
+
+    // the index into self.screens of the monitor covering `point`, or the
+    // first known monitor as a fallback
+    fn screen_index_for_point(&self, point: Point) -> usize {
+        self.screens
+            .iter()
+            .position(|screen| screen.contains(point))
+            .unwrap_or(0)
+    }
+
+    // re-tile every screen's non-floating clients under the current layout
+    fn arrange(&self) {
+        for (index, &region) in self.screens.iter().enumerate() {
+            let tiled: Vec<xcb::Window> = self
+                .client_order
+                .iter()
+                .copied()
+                .filter(|client| {
+                    self.client_screen.get(client) == Some(&index)
+                        && !self.floating.contains(client)
+                })
+                .collect();
+
+            for (client, tile) in self.layout.arrange(region, &tiled, self.master_ratio) {
+                let frame = match self.clients.get(&client) {
+                    Some(&frame) => frame,
+                    None => continue,
+                };
+
+                let value_list = vec![
+                    (xcb::CONFIG_WINDOW_X as u16, tile.x as u32),
+                    (xcb::CONFIG_WINDOW_Y as u16, tile.y as u32),
+                    (xcb::CONFIG_WINDOW_WIDTH as u16, tile.width),
+                    (xcb::CONFIG_WINDOW_HEIGHT as u16, tile.height),
+                ];
+                xcb::configure_window(&self.connection, frame, &value_list);
+
+                // the client fills the frame's interior and sits at (0, 0)
+                // within it, so only its size needs to follow the tile
+                let client_value_list = vec![
+                    (xcb::CONFIG_WINDOW_WIDTH as u16, tile.width),
+                    (xcb::CONFIG_WINDOW_HEIGHT as u16, tile.height),
+                ];
+                xcb::configure_window(&self.connection, client, &client_value_list);
+            }
+        }
+    }
+
+    fn cycle_layout(&mut self) {
+        self.layout = self.layout.next();
+        self.arrange();
+    }
+
+    // move a client to the front of its screen's tiling order, making it the
+    // new master
+    fn promote_to_master(&mut self, window: xcb::Window) {
+        if !self.clients.contains_key(&window) {
+            return;
+        }
+
+        if let Some(pos) = self.client_order.iter().position(|&w| w == window) {
+            self.client_order.remove(pos);
+            self.client_order.insert(0, window);
+        }
+
+        self.arrange();
+    }
+
+    // move a window between the floating and tiled sets; the way back into
+    // tiling for anything an accidental Mod1-drag floated
+    fn toggle_floating(&mut self, window: xcb::Window) {
+        if !self.clients.contains_key(&window) {
+            return;
+        }
+
+        if !self.floating.remove(&window) {
+            self.floating.insert(window);
+        }
+
+        self.arrange();
     }
 }
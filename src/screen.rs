@@ -0,0 +1,116 @@
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+// a rectangle in root coordinates: a monitor's geometry, or a tile that a
+// layout assigns to a client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x
+            && point.x < self.x + self.width as i32
+            && point.y >= self.y
+            && point.y < self.y + self.height as i32
+    }
+}
+
+// enumerate the active CRTCs via RandR 1.2+, falling back to the root
+// window's geometry as a single screen when RandR is unavailable or bare
+pub fn query_screens(connection: &xcb::Connection, root: xcb::Window) -> Vec<Region> {
+    if xcb::randr::query_version(connection, 1, 2)
+        .get_reply()
+        .is_err()
+    {
+        return vec![root_region(connection, root)];
+    }
+
+    let resources = match xcb::randr::get_screen_resources_current(connection, root).get_reply() {
+        Ok(resources) => resources,
+        Err(_) => return vec![root_region(connection, root)],
+    };
+
+    let regions: Vec<Region> = resources
+        .crtcs()
+        .iter()
+        .filter_map(|&crtc| {
+            let info = xcb::randr::get_crtc_info(connection, crtc, 0)
+                .get_reply()
+                .ok()?;
+
+            if info.width() == 0 || info.height() == 0 {
+                return None;
+            }
+
+            Some(Region {
+                x: info.x() as i32,
+                y: info.y() as i32,
+                width: info.width() as u32,
+                height: info.height() as u32,
+            })
+        })
+        .collect();
+
+    if regions.is_empty() {
+        vec![root_region(connection, root)]
+    } else {
+        regions
+    }
+}
+
+fn root_region(connection: &xcb::Connection, root: xcb::Window) -> Region {
+    let geo = xcb::get_geometry(connection, root)
+        .get_reply()
+        .expect("Could not get geometry of root window");
+
+    Region {
+        x: 0,
+        y: 0,
+        width: geo.width() as u32,
+        height: geo.height() as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region() -> Region {
+        Region {
+            x: 10,
+            y: 20,
+            width: 100,
+            height: 50,
+        }
+    }
+
+    #[test]
+    fn contains_a_point_inside_the_region() {
+        assert!(region().contains(Point { x: 50, y: 40 }));
+    }
+
+    #[test]
+    fn contains_is_inclusive_of_the_top_left_corner() {
+        assert!(region().contains(Point { x: 10, y: 20 }));
+    }
+
+    #[test]
+    fn contains_excludes_the_bottom_right_edge() {
+        assert!(!region().contains(Point { x: 110, y: 70 }));
+    }
+
+    #[test]
+    fn contains_rejects_a_point_outside_the_region() {
+        assert!(!region().contains(Point { x: 9, y: 20 }));
+        assert!(!region().contains(Point { x: 10, y: 19 }));
+    }
+}